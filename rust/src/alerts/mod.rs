@@ -0,0 +1,129 @@
+use anyhow::Result;
+use futures_util::{stream, StreamExt};
+use reqwest::Client;
+
+use crate::database::DynamoClient;
+use crate::models::{AlertDirection, StockPrice, WatchlistItem};
+use crate::stocks::QuoteProvider;
+
+/// 通知・DB更新を同時に処理する件数の上限。スケジューラのタイムアウト内に収めるためのバッチサイズ。
+const CONCURRENT_EVALUATIONS: usize = 8;
+
+/// 監視銘柄のアラート閾値を評価し、Discord Webhookへ通知するエバリュエータ。
+/// 同じ側に留まっている間は再通知せず、閾値を割り戻して再度到達したときだけ
+/// 再発火する（ヒステリシス）。
+pub struct AlertEvaluator {
+    dynamo_client: DynamoClient,
+    quote_provider: Box<dyn QuoteProvider>,
+    webhook_url: String,
+    http_client: Client,
+}
+
+impl AlertEvaluator {
+    pub fn new(dynamo_client: DynamoClient, quote_provider: Box<dyn QuoteProvider>, webhook_url: String) -> Self {
+        Self {
+            dynamo_client,
+            quote_provider,
+            webhook_url,
+            http_client: Client::new(),
+        }
+    }
+
+    pub async fn evaluate_all(&self) -> Result<()> {
+        let items = self.dynamo_client.get_alertable_items().await?;
+        tracing::info!("Evaluating {} alert(s)", items.len());
+
+        // 上流APIは銘柄単位のエンドポイントしか持たないが、quotes()にまとめて渡すことで
+        // プロバイダ側が並列度やレートリミットを一箇所で制御できるようにする
+        let symbols: Vec<&str> = items.iter().map(|item| item.symbol.as_str()).collect();
+        let prices = self.quote_provider.quotes(&symbols).await;
+
+        // 通知・DB更新（I/O待ちが大半）は件数分まとめて並列実行する
+        stream::iter(items.iter().zip(prices))
+            .for_each_concurrent(CONCURRENT_EVALUATIONS, |(item, price)| async move {
+                let price = match price {
+                    Ok(price) => price,
+                    Err(e) => {
+                        tracing::error!("Failed to fetch quote for {}/{}: {}", item.user_id, item.symbol, e);
+                        return;
+                    }
+                };
+
+                if let Err(e) = self.evaluate_item(item, price).await {
+                    tracing::error!("Failed to evaluate alert for {}/{}: {}", item.user_id, item.symbol, e);
+                }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    async fn evaluate_item(&self, item: &WatchlistItem, price: StockPrice) -> Result<()> {
+        let threshold = match item.alert_threshold {
+            Some(threshold) => threshold,
+            None => return Ok(()),
+        };
+        let direction = item.alert_direction.unwrap_or(AlertDirection::Above);
+
+        // 市場が閉まっている間は古い/動かない価格でアラートを誤発火させない
+        if let Some(gmt_offset_seconds) = price.gmt_offset_seconds {
+            let clock = crate::market_clock::MarketClock::for_exchange(gmt_offset_seconds);
+            if clock.session(price.timestamp) == crate::market_clock::MarketSession::Closed {
+                tracing::debug!("Market closed for {}, skipping alert evaluation", item.symbol);
+                return Ok(());
+            }
+        }
+
+        let crossed = Self::is_crossed(direction, price.price, threshold);
+        let previously_crossed = item.last_triggered_price
+            .is_some_and(|last| Self::is_crossed(direction, last, threshold));
+
+        if crossed && !previously_crossed {
+            self.notify(item, &price, threshold, direction).await?;
+        }
+
+        if crossed != previously_crossed {
+            self.dynamo_client.record_alert_trigger(&item.user_id, &item.symbol, price.price).await?;
+        }
+
+        Ok(())
+    }
+
+    fn is_crossed(direction: AlertDirection, price: f64, threshold: f64) -> bool {
+        match direction {
+            AlertDirection::Above => price >= threshold,
+            AlertDirection::Below => price <= threshold,
+        }
+    }
+
+    async fn notify(&self, item: &WatchlistItem, price: &StockPrice, threshold: f64, direction: AlertDirection) -> Result<()> {
+        let (verb, color) = match direction {
+            AlertDirection::Above => ("上回りました", 0x2ecc71),
+            AlertDirection::Below => ("下回りました", 0xe74c3c),
+        };
+
+        let change_percent = price.change_percent
+            .map(|pct| format!("{:.2}%", pct))
+            .unwrap_or_else(|| "N/A".to_string());
+
+        let payload = serde_json::json!({
+            "embeds": [{
+                "title": format!("🔔 {} がアラート閾値を{}", item.symbol, verb),
+                "description": format!(
+                    "現在価格: **${:.2}**\n閾値: ${:.2}\n変動率: {}\n<@{}>",
+                    price.price, threshold, change_percent, item.user_id
+                ),
+                "color": color,
+            }]
+        });
+
+        self.http_client
+            .post(&self.webhook_url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
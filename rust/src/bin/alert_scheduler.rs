@@ -0,0 +1,57 @@
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use serde_json::Value;
+use tracing::{info, error, Instrument};
+
+use stock_monitoring_bot::alerts::AlertEvaluator;
+use stock_monitoring_bot::config::Config;
+use stock_monitoring_bot::database::DynamoClient;
+use stock_monitoring_bot::stocks::{AlphaVantageProvider, CachedProvider, FallbackProvider, FinnhubProvider, QuoteProvider, YahooProvider};
+
+async fn handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
+    let (_event, context) = event.into_parts();
+    let request_span = tracing::info_span!("alert_evaluation", request_id = %context.request_id);
+
+    async move {
+        info!("アラート評価開始");
+
+        let config = Config::new().await?;
+        let dynamo_client = DynamoClient::new().await?;
+
+        // Yahoo Financeを優先し、失敗時はAlpha Vantage、さらにFinnhubへとフォールバックする。
+        // TTLキャッシュを被せ、同一銘柄への短時間の連打で上流をレート制限させない。
+        let alpha_vantage_key = config.get_alpha_vantage_api_key().await.unwrap_or_default();
+        let finnhub_key = config.get_finnhub_api_key().await.unwrap_or_default();
+        let fallback = FallbackProvider::new(vec![
+            Box::new(YahooProvider::new()),
+            Box::new(AlphaVantageProvider::new(alpha_vantage_key)),
+            Box::new(FinnhubProvider::new(finnhub_key)),
+        ]);
+        let quote_provider: Box<dyn QuoteProvider> = Box::new(CachedProvider::new(fallback));
+
+        let webhook_url = config.get_discord_webhook_url().await?;
+        let evaluator = AlertEvaluator::new(dynamo_client, quote_provider, webhook_url);
+
+        if let Err(e) = evaluator.evaluate_all().await {
+            error!("アラート評価エラー: {}", e);
+            return Err(e.into());
+        }
+
+        info!("アラート評価完了");
+
+        Ok(serde_json::json!({ "statusCode": 200 }))
+    }
+    .instrument(request_span)
+    .await
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    stock_monitoring_bot::logging::init();
+
+    info!("Alert Scheduler Lambda starting...");
+
+    let func = service_fn(handler);
+    lambda_runtime::run(func).await?;
+
+    Ok(())
+}
@@ -1,150 +1,301 @@
 use lambda_runtime::{service_fn, Error, LambdaEvent};
 // use lambda_web::{is_running_on_lambda, LambdaWeb, Request, RequestExt, Response, Body};
+use base64::Engine;
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use tracing::{info, error, debug};
-use tracing_subscriber;
+use std::time::Instant;
+use tracing::{info, error, debug, Instrument};
 
 use stock_monitoring_bot::config::Config;
-use stock_monitoring_bot::discord::{verify_signature, InteractionHandler};
+use stock_monitoring_bot::discord::{verify_signature, InteractionHandler, VerifyError, DEFAULT_TIMESTAMP_SKEW_SECONDS};
 use stock_monitoring_bot::database::DynamoClient;
-use stock_monitoring_bot::stocks::StockClient;
+use stock_monitoring_bot::error::BotError;
+use stock_monitoring_bot::stocks::{AlphaVantageProvider, CachedProvider, FallbackProvider, FinnhubProvider, QuoteProvider, YahooProvider};
 use stock_monitoring_bot::models::{DiscordInteraction, InteractionResponse};
 
+/// セルフ起動による非同期フォローアップ処理を識別するマーカーキー。
+/// API Gatewayからのリクエストには現れないため、この有無で通常経路と区別する。
+const FOLLOWUP_MARKER: &str = "_followup_interaction";
+
 async fn handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
-    let (event, _context) = event.into_parts();
-    
-    info!("Discord Interactions処理開始");
-    debug!("Event: {}", serde_json::to_string_pretty(&event)?);
-
-    // HTTP リクエストの処理
-    let headers = event.get("headers")
-        .and_then(|h| h.as_object())
-        .unwrap_or(&serde_json::Map::new());
-
-    let body = event.get("body")
-        .and_then(|b| b.as_str())
-        .unwrap_or("");
-
-    // Discord署名ヘッダーを取得
-    let mut signature = String::new();
-    let mut timestamp = String::new();
-
-    for (key, value) in headers {
-        let key_lower = key.to_lowercase();
-        if let Some(val_str) = value.as_str() {
-            match key_lower.as_str() {
-                "x-signature-ed25519" => signature = val_str.to_string(),
-                "x-signature-timestamp" => timestamp = val_str.to_string(),
-                _ => {}
-            }
-        }
+    let (event, context) = event.into_parts();
+    let request_id = context.request_id;
+
+    // 非同期フォローアップ呼び出し（遅延応答の本処理）
+    if let Some(interaction_value) = event.get(FOLLOWUP_MARKER) {
+        let followup_span = tracing::info_span!("discord_followup", request_id = %request_id);
+        return handle_followup(interaction_value.clone()).instrument(followup_span).await;
     }
 
-    debug!("Signature length: {}, Timestamp: {}", signature.len(), timestamp);
+    // interaction_id/application_id/commandはinteractionをパースするまで分からないため、
+    // 未設定（Empty）で開始し、判明し次第`record`でスパンに埋める。
+    let request_span = tracing::info_span!(
+        "discord_interaction",
+        request_id = %request_id,
+        interaction_id = tracing::field::Empty,
+        application_id = tracing::field::Empty,
+        command = tracing::field::Empty,
+    );
 
-    // 設定とクライアント初期化
-    let config = Config::new().await?;
-    let public_key = config.get_discord_public_key().await?;
+    async move {
+        info!("Discord Interactions処理開始");
+        debug!("Event: {}", serde_json::to_string_pretty(&event)?);
+
+        // HTTP リクエストの処理
+        let empty_headers = serde_json::Map::new();
+        let headers = event.get("headers")
+            .and_then(|h| h.as_object())
+            .unwrap_or(&empty_headers);
+
+        let body = event.get("body")
+            .and_then(|b| b.as_str())
+            .unwrap_or("");
 
-    // 署名検証
-    match verify_signature(&signature, &timestamp, body, &public_key) {
-        Ok(true) => {
-            debug!("署名検証成功");
+        // Discord署名ヘッダーを取得
+        let mut signature = String::new();
+        let mut timestamp = String::new();
+
+        for (key, value) in headers {
+            let key_lower = key.to_lowercase();
+            if let Some(val_str) = value.as_str() {
+                match key_lower.as_str() {
+                    "x-signature-ed25519" => signature = val_str.to_string(),
+                    "x-signature-timestamp" => timestamp = val_str.to_string(),
+                    _ => {}
+                }
+            }
         }
-        Ok(false) => {
-            error!("署名検証失敗");
+
+        debug!("Signature length: {}, Timestamp: {}", signature.len(), timestamp);
+
+        // 設定とクライアント初期化
+        let config = Config::new().await?;
+        let public_key = config.get_discord_public_key().await?;
+
+        // 署名検証。HexDecode/InvalidSignatureLength等はリクエスト形式の不備（400）、
+        // SignatureMismatch/StaleTimestampは偽造・リプレイの疑いがある正規リクエスト（401）として区別する。
+        // CloudWatchで遅い署名検証（DynamoDBのリプレイチェック込み）を追えるよう所要時間も記録する。
+        let verify_started_at = Instant::now();
+        if let Err(e) = verify_signature(&signature, &timestamp, body, &public_key, DEFAULT_TIMESTAMP_SKEW_SECONDS) {
+            let status = match e {
+                VerifyError::SignatureMismatch | VerifyError::StaleTimestamp => 401,
+                _ => 400,
+            };
+            error!("署名検証失敗 (status={}): {}", status, e);
             return Ok(json!({
-                "statusCode": 401,
+                "statusCode": status,
                 "headers": {"Content-Type": "application/json"},
-                "body": json!({"error": "Invalid signature"}).to_string()
+                "body": json!({"error": e.to_string()}).to_string()
             }));
         }
-        Err(e) => {
-            error!("署名検証エラー: {}", e);
+        debug!("署名検証成功");
+
+        // リプレイ検知: 同一署名を条件付きPutで記録し、既に使われていれば拒否する
+        match DynamoClient::new().await {
+            Ok(dynamo_client) => match dynamo_client.record_nonce_if_absent(&signature, DEFAULT_TIMESTAMP_SKEW_SECONDS).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    error!("署名のリプレイを検知しました");
+                    return Ok(json!({
+                        "statusCode": 401,
+                        "headers": {"Content-Type": "application/json"},
+                        "body": json!({"error": "replay detected"}).to_string()
+                    }));
+                }
+                Err(e) => {
+                    // リプレイ検知の記録自体が失敗してもリクエストはブロックしない
+                    error!("リプレイ検知の記録に失敗しました: {}", e);
+                }
+            },
+            Err(e) => error!("DynamoClientの初期化に失敗しました: {}", e),
+        }
+        info!(elapsed_ms = verify_started_at.elapsed().as_millis() as u64, "signature verification complete");
+
+        // Discord Interactionをパース
+        let interaction: DiscordInteraction = match serde_json::from_str(body) {
+            Ok(interaction) => interaction,
+            Err(e) => {
+                error!("Interaction parse error: {}", e);
+                return Ok(json!({
+                    "statusCode": 400,
+                    "headers": {"Content-Type": "application/json"},
+                    "body": json!({"error": "Invalid interaction data"}).to_string()
+                }));
+            }
+        };
+
+        debug!("Interaction type: {}", interaction.interaction_type);
+
+        let command_name = interaction.data.as_ref().map(|d| d.name.as_str()).unwrap_or("").to_string();
+        let current_span = tracing::Span::current();
+        current_span.record("interaction_id", interaction.id.as_deref().unwrap_or(""));
+        current_span.record("application_id", interaction.application_id.as_deref().unwrap_or(""));
+        current_span.record("command", command_name.as_str());
+
+        // PING応答（Discord検証用）
+        if interaction.interaction_type == 1 {
+            info!("PING応答");
             return Ok(json!({
-                "statusCode": 401,
+                "statusCode": 200,
                 "headers": {"Content-Type": "application/json"},
-                "body": json!({"error": "Signature verification failed"}).to_string()
+                "body": json!({"type": 1}).to_string()
             }));
         }
-    }
 
-    // Discord Interactionをパース
-    let interaction: DiscordInteraction = match serde_json::from_str(body) {
-        Ok(interaction) => interaction,
-        Err(e) => {
-            error!("Interaction parse error: {}", e);
+        // 外部APIへの問い合わせを伴い3秒のACK期限を超えうるコマンドは、DEFERREDを即座に返し
+        // 本処理は自分自身を非同期呼び出しして行う
+        if interaction.interaction_type == 2 && InteractionHandler::is_deferred_command(&command_name) {
+            if let Err(e) = dispatch_followup_invocation(&interaction).await {
+                error!("フォローアップ呼び出しの起動に失敗: {}", e);
+            }
+            info!("遅延応答を返却: {}", command_name);
             return Ok(json!({
-                "statusCode": 400,
+                "statusCode": 200,
                 "headers": {"Content-Type": "application/json"},
-                "body": json!({"error": "Invalid interaction data"}).to_string()
+                "body": serde_json::to_string(&InteractionResponse::deferred())?
             }));
         }
-    };
 
-    debug!("Interaction type: {}", interaction.interaction_type);
+        // 他のInteractionを処理（銘柄取得を伴うコマンドの所要時間もここに含まれる。
+        // プロバイダ単位の内訳はFallbackProviderが`quote provider call`として個別に記録する）
+        let process_started_at = Instant::now();
+        let outcome = process_interaction(interaction).await;
+        info!(elapsed_ms = process_started_at.elapsed().as_millis() as u64, "interaction processing complete");
+
+        match outcome {
+            Ok(response) => {
+                info!("Discord Interactions処理完了");
+                build_lambda_response(&response)
+            }
+            Err(e) => {
+                error!("Interaction処理エラー: {}", e);
+                let error_response = InteractionResponse::ephemeral_message(e.user_message());
+                build_lambda_response(&error_response)
+            }
+        }
+    }
+    .instrument(request_span)
+    .await
+}
 
-    // PING応答（Discord検証用）
-    if interaction.interaction_type == 1 {
-        info!("PING応答");
+/// ファイル添付が無ければ通常のJSONレスポンス、あればmultipart/form-dataで
+/// `payload_json` + `files[0]` を送る（Discordのインタラクション応答フォーマット）。
+fn build_lambda_response(response: &InteractionResponse) -> Result<Value, Error> {
+    let Some(attachment) = response.data.as_ref().and_then(|d| d.attachment.as_ref()) else {
         return Ok(json!({
             "statusCode": 200,
             "headers": {"Content-Type": "application/json"},
-            "body": json!({"type": 1}).to_string()
+            "body": serde_json::to_string(response)?
         }));
-    }
+    };
 
-    // 他のInteractionを処理
-    match process_interaction(interaction).await {
-        Ok(response) => {
-            let response_json = serde_json::to_string(&response)?;
-            info!("Discord Interactions処理完了");
-            
-            Ok(json!({
-                "statusCode": 200,
-                "headers": {"Content-Type": "application/json"},
-                "body": response_json
-            }))
-        }
-        Err(e) => {
-            error!("Interaction処理エラー: {}", e);
-            let error_response = InteractionResponse::ephemeral_message(
-                "❌ 処理中にエラーが発生しました".to_string()
-            );
-            let response_json = serde_json::to_string(&error_response)?;
-            
-            Ok(json!({
-                "statusCode": 200,
-                "headers": {"Content-Type": "application/json"},
-                "body": response_json
-            }))
-        }
-    }
+    const BOUNDARY: &str = "----StockBotBoundary7MA4YWxkTrZu0gW";
+
+    let payload_json = serde_json::to_string(response)?;
+
+    let mut body = Vec::new();
+    body.extend_from_slice(format!("--{}\r\n", BOUNDARY).as_bytes());
+    body.extend_from_slice(b"Content-Disposition: form-data; name=\"payload_json\"\r\n");
+    body.extend_from_slice(b"Content-Type: application/json\r\n\r\n");
+    body.extend_from_slice(payload_json.as_bytes());
+    body.extend_from_slice(b"\r\n");
+
+    body.extend_from_slice(format!("--{}\r\n", BOUNDARY).as_bytes());
+    body.extend_from_slice(
+        format!(
+            "Content-Disposition: form-data; name=\"files[0]\"; filename=\"{}\"\r\n",
+            attachment.filename
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(format!("Content-Type: {}\r\n\r\n", attachment.content_type).as_bytes());
+    body.extend_from_slice(&attachment.bytes);
+    body.extend_from_slice(b"\r\n");
+    body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+
+    Ok(json!({
+        "statusCode": 200,
+        "headers": {"Content-Type": format!("multipart/form-data; boundary={}", BOUNDARY)},
+        "body": base64::engine::general_purpose::STANDARD.encode(&body),
+        "isBase64Encoded": true
+    }))
 }
 
-async fn process_interaction(interaction: DiscordInteraction) -> Result<InteractionResponse, Box<dyn std::error::Error + Send + Sync>> {
+async fn build_interaction_handler() -> Result<InteractionHandler, BotError> {
     info!("Creating clients...");
-    
-    let dynamo_client = DynamoClient::new().await?;
-    let stock_client = StockClient::new();
-    let interaction_handler = InteractionHandler::new(dynamo_client, stock_client);
+
+    let config = Config::new().await?;
+    let dynamo_client = DynamoClient::new().await.map_err(BotError::Database)?;
+
+    // Yahoo Financeを優先し、失敗時はAlpha Vantage、さらにFinnhubへとフォールバックする。
+    // TTLキャッシュを被せ、同一銘柄への短時間の連打で上流をレート制限させない。
+    let alpha_vantage_key = config.get_alpha_vantage_api_key().await.unwrap_or_default();
+    let finnhub_key = config.get_finnhub_api_key().await.unwrap_or_default();
+    let fallback = FallbackProvider::new(vec![
+        Box::new(YahooProvider::new()),
+        Box::new(AlphaVantageProvider::new(alpha_vantage_key)),
+        Box::new(FinnhubProvider::new(finnhub_key)),
+    ]);
+    let quote_provider: Box<dyn QuoteProvider> = Box::new(CachedProvider::new(fallback));
+
+    Ok(InteractionHandler::new(dynamo_client, quote_provider))
+}
+
+async fn process_interaction(interaction: DiscordInteraction) -> Result<InteractionResponse, BotError> {
+    let interaction_handler = build_interaction_handler().await?;
 
     info!("Processing interaction...");
-    
+
     let response = interaction_handler.handle_interaction(interaction).await?;
-    
+
     Ok(response)
 }
 
+/// 遅延応答対象コマンドの本処理。自分自身への非同期invokeから呼ばれ、結果は
+/// フォローアップWebhookへPATCHする（戻り値はLambdaの呼び出し元からは参照されない）。
+async fn handle_followup(interaction_value: Value) -> Result<Value, Error> {
+    let interaction: DiscordInteraction = serde_json::from_value(interaction_value)?;
+    let application_id = interaction.application_id.clone().unwrap_or_default();
+    let token = interaction.token.clone().unwrap_or_default();
+
+    let interaction_handler = build_interaction_handler().await?;
+
+    let response = match interaction_handler.handle_interaction(interaction).await {
+        Ok(response) => response,
+        Err(e) => {
+            error!("フォローアップ処理エラー: {}", e);
+            InteractionResponse::ephemeral_message(e.user_message())
+        }
+    };
+
+    if let Err(e) = interaction_handler.send_followup(&application_id, &token, &response).await {
+        error!("フォローアップWebhook送信に失敗: {}", e);
+    }
+
+    Ok(json!({"statusCode": 200}))
+}
+
+/// 現在のLambda関数自身を非同期（Event）invokeし、遅延応答の本処理を走らせる。
+async fn dispatch_followup_invocation(interaction: &DiscordInteraction) -> Result<(), Error> {
+    let function_name = std::env::var("AWS_LAMBDA_FUNCTION_NAME")?;
+    let aws_config = aws_config::load_from_env().await;
+    let lambda_client = aws_sdk_lambda::Client::new(&aws_config);
+
+    let payload = json!({ FOLLOWUP_MARKER: interaction });
+
+    lambda_client
+        .invoke()
+        .function_name(function_name)
+        .invocation_type(aws_sdk_lambda::types::InvocationType::Event)
+        .payload(aws_sdk_lambda::primitives::Blob::new(payload.to_string()))
+        .send()
+        .await?;
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    // ログ初期化
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .with_target(false)
-        .without_time()
-        .init();
+    stock_monitoring_bot::logging::init();
 
     info!("Discord Handler Lambda starting...");
 
@@ -0,0 +1,93 @@
+use anyhow::{Result, anyhow};
+use image::codecs::png::PngEncoder;
+use image::{ColorType, ImageEncoder};
+use plotters::prelude::*;
+
+use crate::models::StockPrice;
+
+const WIDTH: u32 = 900;
+const HEIGHT: u32 = 600;
+
+/// 銘柄の時系列データからローソク足チャート（出来高サブパネル付き）を描画し、
+/// PNGバイト列として返す。
+pub fn render_candlestick(symbol: &str, prices: &[StockPrice]) -> Result<Vec<u8>> {
+    if prices.is_empty() {
+        return Err(anyhow!("No historical data to render for {}", symbol));
+    }
+
+    let mut buffer = vec![0u8; (WIDTH * HEIGHT * 3) as usize];
+
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (WIDTH, HEIGHT)).into_drawing_area();
+        root.fill(&WHITE)?;
+        let (price_area, volume_area) = root.split_vertically(HEIGHT * 3 / 4);
+
+        let min_date = prices.first().unwrap().timestamp;
+        let max_date = prices.last().unwrap().timestamp;
+
+        let low = prices.iter().filter_map(|p| p.low_price).fold(f64::MAX, f64::min);
+        let high = prices.iter().filter_map(|p| p.high_price).fold(f64::MIN, f64::max);
+        let padding = ((high - low) * 0.05).max(0.01);
+
+        let mut price_chart = ChartBuilder::on(&price_area)
+            .caption(format!("{} ローソク足チャート", symbol), ("sans-serif", 24))
+            .margin(10)
+            .x_label_area_size(0)
+            .y_label_area_size(60)
+            .build_cartesian_2d(min_date..max_date, (low - padding)..(high + padding))?;
+
+        price_chart.configure_mesh().y_desc("Price ($)").draw()?;
+
+        price_chart.draw_series(prices.iter().filter_map(|p| {
+            let (open, high, low) = (p.open_price?, p.high_price?, p.low_price?);
+            Some(CandleStick::new(
+                p.timestamp,
+                open,
+                high,
+                low,
+                p.price,
+                GREEN.filled(),
+                RED.filled(),
+                5,
+            ))
+        }))?;
+
+        let max_volume = prices.iter().filter_map(|p| p.volume).max().unwrap_or(1).max(1);
+
+        let mut volume_chart = ChartBuilder::on(&volume_area)
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(min_date..max_date, 0u64..max_volume)?;
+
+        volume_chart.configure_mesh().y_desc("Volume").draw()?;
+
+        // 1本あたりの間隔の半分をバー幅とする（ローソク足と同じ銘柄数・期間を前提に、
+        // 各バーが隣と重ならずに見えるようにするため）。
+        let bar_half_width = if prices.len() > 1 {
+            (max_date - min_date) / (4 * (prices.len() as i32 - 1))
+        } else {
+            chrono::Duration::hours(12)
+        };
+
+        volume_chart.draw_series(prices.iter().filter_map(|p| {
+            let volume = p.volume?;
+            let is_up = p.open_price.is_none_or(|open| p.price >= open);
+            let color = if is_up { GREEN.filled() } else { RED.filled() };
+            Some(Rectangle::new(
+                [(p.timestamp - bar_half_width, 0), (p.timestamp + bar_half_width, volume)],
+                color,
+            ))
+        }))?;
+
+        root.present()?;
+    }
+
+    encode_png(&buffer, WIDTH, HEIGHT)
+}
+
+fn encode_png(buffer: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+    let mut png_bytes = Vec::new();
+    PngEncoder::new(&mut png_bytes).write_image(buffer, width, height, ColorType::Rgb8)?;
+    Ok(png_bytes)
+}
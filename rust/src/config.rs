@@ -46,4 +46,23 @@ impl Config {
     pub async fn get_alpha_vantage_api_key(&self) -> Result<String> {
         self.get_parameter("alpha-vantage-api-key").await
     }
+
+    pub async fn get_finnhub_api_key(&self) -> Result<String> {
+        self.get_parameter("finnhub-api-key").await
+    }
+
+    /// ログレベル。`LOG_LEVEL` 環境変数から読む（未設定・不正値時はINFO）。
+    /// ログ初期化は`Config::new()`より前に行われるため、SSMではなく環境変数で設定する。
+    pub fn log_level() -> tracing::Level {
+        std::env::var("LOG_LEVEL")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(tracing::Level::INFO)
+    }
+
+    /// CloudWatch Logs Insightsでのクエリを想定し、デフォルトはJSON形式。
+    /// ローカル実行時など人間が読みやすい形式が欲しい場合は`LOG_FORMAT=pretty`を設定する。
+    pub fn log_json() -> bool {
+        std::env::var("LOG_FORMAT").map(|v| v != "pretty").unwrap_or(true)
+    }
 }
\ No newline at end of file
@@ -2,26 +2,47 @@ use anyhow::Result;
 use aws_sdk_dynamodb::Client as DynamoDbClient;
 use aws_sdk_dynamodb::types::AttributeValue;
 use chrono::Utc;
-use crate::models::WatchlistItem;
+use crate::models::{AlertDirection, WatchlistItem};
 
 pub struct DynamoClient {
     client: DynamoDbClient,
     stocks_table: String,
-    alerts_table: String,
+    nonces_table: String,
 }
 
 impl DynamoClient {
     pub async fn new() -> Result<Self> {
         let aws_config = aws_config::load_from_env().await;
         let client = DynamoDbClient::new(&aws_config);
-        
+
         Ok(Self {
             client,
             stocks_table: "stock-monitoring-bot-stocks-dev".to_string(),
-            alerts_table: "stock-monitoring-bot-alerts-dev".to_string(),
+            nonces_table: "stock-monitoring-bot-nonces-dev".to_string(),
         })
     }
 
+    /// 署名のリプレイ検知。同じ署名を条件付きPutで記録し、既に記録済み（= リプレイ）なら
+    /// `false` を返す。テーブル側の `expires_at` をTTL属性に設定しておけば自動的に失効する。
+    pub async fn record_nonce_if_absent(&self, signature: &str, ttl_seconds: i64) -> Result<bool> {
+        let expires_at = Utc::now().timestamp() + ttl_seconds;
+
+        let result = self.client
+            .put_item()
+            .table_name(&self.nonces_table)
+            .item("signature", AttributeValue::S(signature.to_string()))
+            .item("expires_at", AttributeValue::N(expires_at.to_string()))
+            .condition_expression("attribute_not_exists(signature)")
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(e) if is_conditional_check_failed(&e) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     pub async fn get_user_watchlist(&self, user_id: &str) -> Result<Vec<WatchlistItem>> {
         let response = self.client
             .scan()
@@ -75,7 +96,13 @@ impl DynamoClient {
         Ok(response.attributes.is_some())
     }
 
-    pub async fn set_alert_threshold(&self, user_id: &str, symbol: &str, threshold: f64) -> Result<()> {
+    pub async fn set_alert_threshold(
+        &self,
+        user_id: &str,
+        symbol: &str,
+        threshold: f64,
+        direction: AlertDirection,
+    ) -> Result<()> {
         // まず監視リストに銘柄があるかチェック
         let existing = self.client
             .get_item()
@@ -90,17 +117,58 @@ impl DynamoClient {
             self.add_to_watchlist(user_id, symbol).await?;
         }
 
-        // アラート閾値を更新
+        // アラート閾値と方向を更新し、発火状態はリセットする
         self.client
             .update_item()
             .table_name(&self.stocks_table)
             .key("user_id", AttributeValue::S(user_id.to_string()))
             .key("symbol", AttributeValue::S(symbol.to_string()))
-            .update_expression("SET alert_threshold = :threshold")
+            .update_expression("SET alert_threshold = :threshold, alert_direction = :direction REMOVE last_triggered_at, last_triggered_price")
             .expression_attribute_values(
-                ":threshold", 
+                ":threshold",
                 AttributeValue::N(threshold.to_string())
             )
+            .expression_attribute_values(
+                ":direction",
+                AttributeValue::S(direction_to_str(direction).to_string())
+            )
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// アラート閾値が設定されている全ユーザー・全銘柄を取得する（スケジューラ用）。
+    pub async fn get_alertable_items(&self) -> Result<Vec<WatchlistItem>> {
+        let response = self.client
+            .scan()
+            .table_name(&self.stocks_table)
+            .filter_expression("attribute_exists(alert_threshold)")
+            .send()
+            .await?;
+
+        let mut items = Vec::new();
+        if let Some(raw_items) = response.items {
+            for item in raw_items {
+                if let Some(watchlist_item) = self.parse_watchlist_item(item)? {
+                    items.push(watchlist_item);
+                }
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// アラート発火状態を記録し、価格が再び閾値を跨ぐまで再通知しないようにする（ヒステリシス）。
+    pub async fn record_alert_trigger(&self, user_id: &str, symbol: &str, price: f64) -> Result<()> {
+        self.client
+            .update_item()
+            .table_name(&self.stocks_table)
+            .key("user_id", AttributeValue::S(user_id.to_string()))
+            .key("symbol", AttributeValue::S(symbol.to_string()))
+            .update_expression("SET last_triggered_at = :now, last_triggered_price = :price")
+            .expression_attribute_values(":now", AttributeValue::S(Utc::now().to_rfc3339()))
+            .expression_attribute_values(":price", AttributeValue::N(price.to_string()))
             .send()
             .await?;
 
@@ -128,11 +196,97 @@ impl DynamoClient {
             _ => None,
         };
 
+        let alert_direction = match item.get("alert_direction") {
+            Some(AttributeValue::S(s)) => str_to_direction(s),
+            _ => None,
+        };
+
+        let last_triggered_at = match item.get("last_triggered_at") {
+            Some(AttributeValue::S(s)) => chrono::DateTime::parse_from_rfc3339(s).ok().map(|dt| dt.with_timezone(&Utc)),
+            _ => None,
+        };
+
+        let last_triggered_price = match item.get("last_triggered_price") {
+            Some(AttributeValue::N(n)) => n.parse::<f64>().ok(),
+            _ => None,
+        };
+
         Ok(Some(WatchlistItem {
             user_id,
             symbol,
             added_at,
             alert_threshold,
+            alert_direction,
+            last_triggered_at,
+            last_triggered_price,
         }))
     }
+}
+
+fn direction_to_str(direction: AlertDirection) -> &'static str {
+    match direction {
+        AlertDirection::Above => "above",
+        AlertDirection::Below => "below",
+    }
+}
+
+fn str_to_direction(s: &str) -> Option<AlertDirection> {
+    match s {
+        "above" => Some(AlertDirection::Above),
+        "below" => Some(AlertDirection::Below),
+        _ => None,
+    }
+}
+
+// `R`（生のHTTPレスポンス型）をジェネリックにしておくことで、テストでは
+// 実際のHTTPレスポンスを組み立てずに`()`を`raw`として使える。
+fn is_conditional_check_failed<R>(
+    err: &aws_sdk_dynamodb::error::SdkError<aws_sdk_dynamodb::operation::put_item::PutItemError, R>,
+) -> bool {
+    matches!(
+        err,
+        aws_sdk_dynamodb::error::SdkError::ServiceError(service_err)
+            if matches!(
+                service_err.err(),
+                aws_sdk_dynamodb::operation::put_item::PutItemError::ConditionalCheckFailedException(_)
+            )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_dynamodb::error::SdkError;
+    use aws_sdk_dynamodb::operation::put_item::PutItemError;
+    use aws_sdk_dynamodb::types::error::{ConditionalCheckFailedException, ResourceNotFoundException};
+
+    /// 署名のリプレイ（=条件付きPutが失敗）を検知できること。
+    #[test]
+    fn detects_conditional_check_failed_as_replay() {
+        let err = SdkError::<_, ()>::service_error(
+            PutItemError::ConditionalCheckFailedException(ConditionalCheckFailedException::builder().build()),
+            (),
+        );
+
+        assert!(is_conditional_check_failed(&err));
+    }
+
+    /// 条件付きPut以外のサービスエラー（一時的な障害など）はリプレイ扱いしないこと。
+    #[test]
+    fn does_not_treat_other_service_errors_as_replay() {
+        let err = SdkError::<_, ()>::service_error(
+            PutItemError::ResourceNotFoundException(ResourceNotFoundException::builder().build()),
+            (),
+        );
+
+        assert!(!is_conditional_check_failed(&err));
+    }
+
+    /// ネットワーク断などディスパッチ自体に失敗したケースもリプレイ扱いしないこと。
+    #[test]
+    fn does_not_treat_dispatch_failure_as_replay() {
+        let err: SdkError<PutItemError, ()> = SdkError::timeout_error("request timed out");
+
+        assert!(!is_conditional_check_failed(&err));
+    }
 }
\ No newline at end of file
@@ -1,21 +1,36 @@
-use anyhow::Result;
-use crate::models::{DiscordInteraction, InteractionResponse};
+use reqwest::Client;
+use crate::error::BotError;
+use crate::models::{ActionRow, Button, DiscordInteraction, InteractionResponse, WatchlistItem};
 use crate::database::DynamoClient;
-use crate::stocks::StockClient;
+use crate::stocks::QuoteProvider;
+
+type Result<T> = std::result::Result<T, BotError>;
+
+/// 1ページに表示する監視銘柄の件数（`/list` のPrev/Nextページネーション用）
+const WATCHLIST_PAGE_SIZE: usize = 5;
 
 pub struct InteractionHandler {
     pub dynamo_client: DynamoClient,
-    pub stock_client: StockClient,
+    pub quote_provider: Box<dyn QuoteProvider>,
+    http_client: Client,
 }
 
 impl InteractionHandler {
-    pub fn new(dynamo_client: DynamoClient, stock_client: StockClient) -> Self {
+    pub fn new(dynamo_client: DynamoClient, quote_provider: Box<dyn QuoteProvider>) -> Self {
         Self {
             dynamo_client,
-            stock_client,
+            quote_provider,
+            http_client: Client::new(),
         }
     }
 
+    /// 外部APIへの問い合わせを伴い、3秒のACK期限を超えうるコマンドかどうか。
+    /// 該当する場合、呼び出し側は即座にDEFERRED応答を返し、フォローアップWebhookで
+    /// 本当の内容を送る必要がある。
+    pub fn is_deferred_command(name: &str) -> bool {
+        matches!(name, "price" | "chart")
+    }
+
     pub async fn handle_interaction(&self, interaction: DiscordInteraction) -> Result<InteractionResponse> {
         // PING応答
         if interaction.interaction_type == 1 {
@@ -27,14 +42,40 @@ impl InteractionHandler {
             return self.handle_application_command(interaction).await;
         }
 
+        // ボタン等のメッセージコンポーネント
+        if interaction.interaction_type == 3 {
+            return self.handle_message_component(interaction).await;
+        }
+
         Ok(InteractionResponse::ephemeral_message(
             "❌ 未対応の操作タイプです".to_string()
         ))
     }
 
+    /// フォローアップWebhookへ実際の応答内容を送信する。ファイル添付がある場合は
+    /// `build_lambda_response` と同様にmultipart/form-dataで送る。
+    pub async fn send_followup(&self, application_id: &str, token: &str, response: &InteractionResponse) -> anyhow::Result<()> {
+        let url = format!("https://discord.com/api/v10/webhooks/{}/{}/messages/@original", application_id, token);
+
+        let Some(attachment) = response.data.as_ref().and_then(|d| d.attachment.as_ref()) else {
+            self.http_client.patch(&url).json(response).send().await?.error_for_status()?;
+            return Ok(());
+        };
+
+        let part = reqwest::multipart::Part::bytes(attachment.bytes.clone())
+            .file_name(attachment.filename.clone())
+            .mime_str(&attachment.content_type)?;
+        let form = reqwest::multipart::Form::new()
+            .text("payload_json", serde_json::to_string(response)?)
+            .part("files[0]", part);
+
+        self.http_client.patch(&url).multipart(form).send().await?.error_for_status()?;
+        Ok(())
+    }
+
     async fn handle_application_command(&self, interaction: DiscordInteraction) -> Result<InteractionResponse> {
-        let data = interaction.data.ok_or_else(|| anyhow::anyhow!("Missing interaction data"))?;
         let user_id = self.get_user_id(&interaction)?;
+        let data = interaction.data.ok_or_else(|| BotError::InvalidInteraction("missing interaction data".to_string()))?;
 
         match data.name.as_str() {
             "list" => self.handle_list_command(&user_id).await,
@@ -60,7 +101,7 @@ impl InteractionHandler {
             return Ok(user.id.clone());
         }
 
-        Err(anyhow::anyhow!("User ID not found"))
+        Err(BotError::InvalidInteraction("user id not found".to_string()))
     }
 
     fn get_option_value(&self, data: &crate::models::InteractionData, name: &str) -> Option<String> {
@@ -81,15 +122,8 @@ impl InteractionHandler {
                         "📊 監視銘柄はありません。\n`/add` コマンドで銘柄を追加してください。".to_string()
                     ))
                 } else {
-                    let mut content = "📊 あなたの監視銘柄:\n".to_string();
-                    for item in watchlist {
-                        content.push_str(&format!("• {} ", item.symbol));
-                        if let Some(threshold) = item.alert_threshold {
-                            content.push_str(&format!("(アラート: ${:.2})", threshold));
-                        }
-                        content.push('\n');
-                    }
-                    Ok(InteractionResponse::message(content))
+                    let (content, components) = Self::build_watchlist_page(user_id, &watchlist, 0);
+                    Ok(InteractionResponse::message_with_components(content, components))
                 }
             }
             Err(e) => {
@@ -101,6 +135,67 @@ impl InteractionHandler {
         }
     }
 
+    /// 監視銘柄一覧の指定ページ分の本文と、Prev/Nextボタンの行を組み立てる。
+    /// `custom_id` にコマンド実行者のIDを埋め込み、他ユーザーのページ送りを拒否できるようにする。
+    fn build_watchlist_page(user_id: &str, watchlist: &[WatchlistItem], page: usize) -> (String, Vec<ActionRow>) {
+        let total_pages = watchlist.len().div_ceil(WATCHLIST_PAGE_SIZE).max(1);
+        let page = page.min(total_pages - 1);
+        let start = page * WATCHLIST_PAGE_SIZE;
+        let end = (start + WATCHLIST_PAGE_SIZE).min(watchlist.len());
+
+        let mut content = format!("📊 あなたの監視銘柄（{}/{}ページ）:\n", page + 1, total_pages);
+        for item in &watchlist[start..end] {
+            content.push_str(&format!("• {} ", item.symbol));
+            if let Some(threshold) = item.alert_threshold {
+                content.push_str(&format!("(アラート: ${:.2})", threshold));
+            }
+            content.push('\n');
+        }
+
+        let prev = Button::new("◀ Prev", format!("watchlist:page:{}:{}", page.saturating_sub(1), user_id))
+            .disabled(page == 0);
+        let next = Button::new("Next ▶", format!("watchlist:page:{}:{}", (page + 1).min(total_pages - 1), user_id))
+            .disabled(page + 1 >= total_pages);
+
+        (content, vec![ActionRow::new(vec![prev, next])])
+    }
+
+    async fn handle_message_component(&self, interaction: DiscordInteraction) -> Result<InteractionResponse> {
+        let data = interaction.data.as_ref().ok_or_else(|| BotError::InvalidInteraction("missing interaction data".to_string()))?;
+        let custom_id = data.custom_id.clone().unwrap_or_default();
+        let clicking_user_id = self.get_user_id(&interaction)?;
+
+        let mut parts = custom_id.split(':');
+        match (parts.next(), parts.next()) {
+            (Some("watchlist"), Some("page")) => {
+                let page: usize = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+                let owner_user_id = parts.next().unwrap_or_default();
+
+                if owner_user_id != clicking_user_id {
+                    return Ok(InteractionResponse::ephemeral_message(
+                        "❌ この操作はコマンドを実行した本人のみ行えます".to_string()
+                    ));
+                }
+
+                match self.dynamo_client.get_user_watchlist(&clicking_user_id).await {
+                    Ok(watchlist) => {
+                        let (content, components) = Self::build_watchlist_page(&clicking_user_id, &watchlist, page);
+                        Ok(InteractionResponse::update_message(content, components))
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to get watchlist: {}", e);
+                        Ok(InteractionResponse::ephemeral_message(
+                            "❌ 監視リストの取得に失敗しました".to_string()
+                        ))
+                    }
+                }
+            }
+            _ => Ok(InteractionResponse::ephemeral_message(
+                format!("❌ 未知の操作です: {}", custom_id)
+            )),
+        }
+    }
+
     async fn handle_add_command(&self, data: &crate::models::InteractionData, user_id: &str) -> Result<InteractionResponse> {
         let symbol = match self.get_option_value(data, "symbol") {
             Some(s) => s.to_uppercase().trim().to_string(),
@@ -115,13 +210,15 @@ impl InteractionHandler {
             ));
         }
 
-        // 銘柄の存在確認（Yahoo Financeで検証）
-        match self.stock_client.validate_symbol(&symbol).await {
+        // 銘柄の存在確認（登録済みプロバイダで検証）
+        match self.quote_provider.validate_symbol(&symbol).await {
             Ok(true) => {
                 match self.dynamo_client.add_to_watchlist(user_id, &symbol).await {
-                    Ok(()) => Ok(InteractionResponse::message(
-                        format!("✅ 銘柄 {} を監視リストに追加しました", symbol)
-                    )),
+                    Ok(()) => {
+                        Ok(InteractionResponse::message(
+                            format!("✅ 銘柄 {} を監視リストに追加しました", symbol)
+                        ))
+                    }
                     Err(e) => {
                         tracing::error!("Failed to add to watchlist: {}", e);
                         Ok(InteractionResponse::ephemeral_message(
@@ -151,9 +248,11 @@ impl InteractionHandler {
         };
 
         match self.dynamo_client.remove_from_watchlist(user_id, &symbol).await {
-            Ok(true) => Ok(InteractionResponse::message(
-                format!("✅ 銘柄 {} を監視リストから削除しました", symbol)
-            )),
+            Ok(true) => {
+                Ok(InteractionResponse::message(
+                    format!("✅ 銘柄 {} を監視リストから削除しました", symbol)
+                ))
+            }
             Ok(false) => Ok(InteractionResponse::ephemeral_message(
                 format!("❌ 銘柄 {} は監視リストにありません", symbol)
             )),
@@ -174,7 +273,7 @@ impl InteractionHandler {
             )),
         };
 
-        match self.stock_client.get_current_price(&symbol).await {
+        match self.quote_provider.current_price(&symbol).await {
             Ok(stock_price) => {
                 let mut content = format!("📈 **{}** の現在価格\n", symbol);
                 content.push_str(&format!("💰 **${:.2}**\n", stock_price.price));
@@ -185,11 +284,26 @@ impl InteractionHandler {
                 }
 
                 if let Some(volume) = stock_price.volume {
-                    content.push_str(&format!("📊 出来高: {:,}\n", volume));
+                    content.push_str(&format!("📊 出来高: {}\n", format_with_commas(volume)));
                 }
 
                 content.push_str(&format!("🕐 {}", stock_price.timestamp.format("%Y-%m-%d %H:%M:%S UTC")));
 
+                if let Some(gmt_offset_seconds) = stock_price.gmt_offset_seconds {
+                    let clock = crate::market_clock::MarketClock::for_exchange(gmt_offset_seconds);
+                    let session_label = match clock.session(stock_price.timestamp) {
+                        crate::market_clock::MarketSession::Open => "🟢 取引時間中",
+                        crate::market_clock::MarketSession::PreMarket => "🌅 プレマーケット",
+                        crate::market_clock::MarketSession::AfterHours => "🌙 時間外取引",
+                        crate::market_clock::MarketSession::Closed => "⚪ 市場は閉まっています",
+                    };
+                    content.push_str(&format!("\n{}", session_label));
+                }
+
+                if stock_price.is_stale {
+                    content.push_str("\n⚠️ 最新データの取得に失敗したため、キャッシュされた価格を表示しています");
+                }
+
                 Ok(InteractionResponse::message(content))
             }
             Err(e) => {
@@ -221,9 +335,22 @@ impl InteractionHandler {
             )),
         };
 
-        match self.dynamo_client.set_alert_threshold(user_id, &symbol, threshold).await {
+        let direction = match self.get_option_value(data, "direction").as_deref() {
+            Some("below") => crate::models::AlertDirection::Below,
+            Some("above") | None => crate::models::AlertDirection::Above,
+            Some(_) => return Ok(InteractionResponse::ephemeral_message(
+                "❌ direction は above または below を指定してください".to_string()
+            )),
+        };
+
+        let direction_label = match direction {
+            crate::models::AlertDirection::Above => "以上",
+            crate::models::AlertDirection::Below => "以下",
+        };
+
+        match self.dynamo_client.set_alert_threshold(user_id, &symbol, threshold, direction).await {
             Ok(()) => Ok(InteractionResponse::message(
-                format!("🔔 銘柄 {} のアラートを ${:.2} に設定しました", symbol, threshold)
+                format!("🔔 銘柄 {} のアラートを ${:.2} {} に設定しました", symbol, threshold, direction_label)
             )),
             Err(e) => {
                 tracing::error!("Failed to set alert: {}", e);
@@ -234,9 +361,65 @@ impl InteractionHandler {
         }
     }
 
-    async fn handle_chart_command(&self, _data: &crate::models::InteractionData) -> Result<InteractionResponse> {
-        Ok(InteractionResponse::ephemeral_message(
-            "📈 チャート機能は準備中です。しばらくお待ちください。".to_string()
-        ))
+    async fn handle_chart_command(&self, data: &crate::models::InteractionData) -> Result<InteractionResponse> {
+        let symbol = match self.get_option_value(data, "symbol") {
+            Some(s) => s.to_uppercase().trim().to_string(),
+            None => return Ok(InteractionResponse::ephemeral_message(
+                "❌ 銘柄コードを指定してください".to_string()
+            )),
+        };
+
+        let period = match self.get_option_value(data, "period").as_deref() {
+            Some(p @ ("1mo" | "3mo" | "6mo" | "1y")) => p.to_string(),
+            None => "3mo".to_string(),
+            Some(_) => return Ok(InteractionResponse::ephemeral_message(
+                "❌ period は 1mo, 3mo, 6mo, 1y のいずれかを指定してください".to_string()
+            )),
+        };
+
+        let prices = match self.quote_provider.historical(&symbol, &period).await {
+            Ok(prices) if !prices.is_empty() => prices,
+            Ok(_) => return Ok(InteractionResponse::ephemeral_message(
+                format!("❌ 銘柄 {} の履歴データが見つかりません", symbol)
+            )),
+            Err(e) => {
+                tracing::error!("Failed to get historical prices: {}", e);
+                return Ok(InteractionResponse::ephemeral_message(
+                    format!("❌ 銘柄 {} のチャート取得に失敗しました", symbol)
+                ));
+            }
+        };
+
+        match crate::chart::render_candlestick(&symbol, &prices) {
+            Ok(png_bytes) => Ok(InteractionResponse::message_with_attachment(
+                format!("📈 **{}** のチャート（{}）", symbol, period),
+                crate::models::ChartAttachment {
+                    filename: format!("{}_{}.png", symbol, period),
+                    content_type: "image/png".to_string(),
+                    bytes: png_bytes,
+                },
+            )),
+            Err(e) => {
+                tracing::error!("Failed to render chart: {}", e);
+                Ok(InteractionResponse::ephemeral_message(
+                    format!("❌ 銘柄 {} のチャート描画に失敗しました", symbol)
+                ))
+            }
+        }
+    }
+}
+
+/// 出来高表示用に3桁ごとにカンマ区切りを入れる（Rustの`format!`は`{:,}`をサポートしないため）。
+fn format_with_commas(n: u64) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (i, c) in digits.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
     }
+
+    grouped.chars().rev().collect()
 }
\ No newline at end of file
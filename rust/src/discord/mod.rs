@@ -1,5 +1,5 @@
 pub mod signature;
 pub mod interactions;
 
-pub use signature::verify_signature;
+pub use signature::{verify_signature, VerifyError, DEFAULT_TIMESTAMP_SKEW_SECONDS};
 pub use interactions::InteractionHandler;
\ No newline at end of file
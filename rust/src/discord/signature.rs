@@ -1,45 +1,165 @@
-use anyhow::{Result, anyhow};
+use chrono::Utc;
 use ed25519_dalek::{Verifier, VerifyingKey, Signature};
+use thiserror::Error;
 
+/// `x-signature-timestamp` とサーバー時刻との許容ずれ（秒）のデフォルト値。
+/// 捕獲された有効なリクエストを無期限に再送できてしまわないよう、これを超えると拒否する。
+pub const DEFAULT_TIMESTAMP_SKEW_SECONDS: i64 = 300;
+
+/// Discordインタラクションの署名検証で起こりうる失敗を区別するための型。
+/// `SignatureMismatch`/`StaleTimestamp` は偽造・リプレイの疑いがある一方、それ以外は
+/// リクエストの形式不備（クライアント/設定側のミス）であり、呼び出し側で扱いを分けられる。
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    #[error("signature must be 128 hex characters")]
+    InvalidSignatureLength,
+    #[error("public key must be 64 hex characters")]
+    InvalidPublicKeyLength,
+    #[error("timestamp is not a valid unix epoch")]
+    InvalidTimestamp,
+    #[error("timestamp skew exceeds the allowed window")]
+    StaleTimestamp,
+    #[error("failed to hex-decode signature or public key")]
+    HexDecode(#[from] hex::FromHexError),
+    #[error("failed to parse ed25519 key or signature bytes")]
+    KeyParse,
+    #[error("signature does not match payload")]
+    SignatureMismatch,
+}
+
+/// `max_skew_seconds` は `x-signature-timestamp` とサーバー時刻との許容ずれ。
+/// 呼び出し側が設定可能にできるよう引数として受け取る（通常は`DEFAULT_TIMESTAMP_SKEW_SECONDS`）。
 pub fn verify_signature(
     signature_hex: &str,
     timestamp: &str,
     body: &str,
     public_key_hex: &str,
-) -> Result<bool> {
-    // 署名長チェック
+    max_skew_seconds: i64,
+) -> Result<(), VerifyError> {
     if signature_hex.len() != 128 {
-        return Ok(false);
+        return Err(VerifyError::InvalidSignatureLength);
     }
 
-    // 公開鍵長チェック
     if public_key_hex.len() != 64 {
-        return Ok(false);
+        return Err(VerifyError::InvalidPublicKeyLength);
     }
 
-    // Hex文字列をバイトに変換
-    let signature_bytes = hex::decode(signature_hex)
-        .map_err(|_| anyhow!("Invalid signature hex"))?;
-    let public_key_bytes = hex::decode(public_key_hex)
-        .map_err(|_| anyhow!("Invalid public key hex"))?;
+    let timestamp_epoch: i64 = timestamp.parse().map_err(|_| VerifyError::InvalidTimestamp)?;
+    let skew = (Utc::now().timestamp() - timestamp_epoch).abs();
+    if skew > max_skew_seconds {
+        return Err(VerifyError::StaleTimestamp);
+    }
+
+    let signature_bytes = hex::decode(signature_hex)?;
+    let public_key_bytes = hex::decode(public_key_hex)?;
 
-    // Ed25519キーと署名を作成
     let verifying_key = VerifyingKey::from_bytes(
-        &public_key_bytes.try_into()
-            .map_err(|_| anyhow!("Invalid public key length"))?
-    ).map_err(|_| anyhow!("Invalid public key"))?;
+        &public_key_bytes.try_into().map_err(|_| VerifyError::KeyParse)?
+    ).map_err(|_| VerifyError::KeyParse)?;
 
     let signature = Signature::from_bytes(
-        &signature_bytes.try_into()
-            .map_err(|_| anyhow!("Invalid signature length"))?
+        &signature_bytes.try_into().map_err(|_| VerifyError::KeyParse)?
     );
 
-    // メッセージを構築
     let message = format!("{}{}", timestamp, body);
 
-    // 署名検証
-    match verifying_key.verify(message.as_bytes(), &signature) {
-        Ok(()) => Ok(true),
-        Err(_) => Ok(false),
+    verifying_key
+        .verify(message.as_bytes(), &signature)
+        .map_err(|_| VerifyError::SignatureMismatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    /// テスト用の固定鍵ペア。`rand_core`機能を追加せずに済むよう、乱数生成ではなく
+    /// 固定のシード（32バイト）から`SigningKey`を組み立てる。
+    fn test_keypair() -> (SigningKey, String) {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key_hex = hex::encode(signing_key.verifying_key().to_bytes());
+        (signing_key, public_key_hex)
+    }
+
+    fn sign(signing_key: &SigningKey, timestamp: &str, body: &str) -> String {
+        let message = format!("{}{}", timestamp, body);
+        hex::encode(signing_key.sign(message.as_bytes()).to_bytes())
+    }
+
+    #[test]
+    fn rejects_signature_of_wrong_length() {
+        let (_signing_key, public_key_hex) = test_keypair();
+        let timestamp = Utc::now().timestamp().to_string();
+
+        let result = verify_signature("deadbeef", &timestamp, "{}", &public_key_hex, DEFAULT_TIMESTAMP_SKEW_SECONDS);
+
+        assert!(matches!(result, Err(VerifyError::InvalidSignatureLength)));
+    }
+
+    #[test]
+    fn rejects_public_key_of_wrong_length() {
+        let (signing_key, _public_key_hex) = test_keypair();
+        let timestamp = Utc::now().timestamp().to_string();
+        let signature_hex = sign(&signing_key, &timestamp, "{}");
+
+        let result = verify_signature(&signature_hex, &timestamp, "{}", "abcd", DEFAULT_TIMESTAMP_SKEW_SECONDS);
+
+        assert!(matches!(result, Err(VerifyError::InvalidPublicKeyLength)));
+    }
+
+    #[test]
+    fn rejects_non_hex_signature() {
+        let (_signing_key, public_key_hex) = test_keypair();
+        let timestamp = Utc::now().timestamp().to_string();
+        let non_hex_signature = "z".repeat(128);
+
+        let result = verify_signature(&non_hex_signature, &timestamp, "{}", &public_key_hex, DEFAULT_TIMESTAMP_SKEW_SECONDS);
+
+        assert!(matches!(result, Err(VerifyError::HexDecode(_))));
+    }
+
+    #[test]
+    fn rejects_timestamp_outside_skew_window() {
+        let (signing_key, public_key_hex) = test_keypair();
+        let stale_timestamp = (Utc::now().timestamp() - DEFAULT_TIMESTAMP_SKEW_SECONDS - 1).to_string();
+        let signature_hex = sign(&signing_key, &stale_timestamp, "{}");
+
+        let result = verify_signature(&signature_hex, &stale_timestamp, "{}", &public_key_hex, DEFAULT_TIMESTAMP_SKEW_SECONDS);
+
+        assert!(matches!(result, Err(VerifyError::StaleTimestamp)));
+    }
+
+    #[test]
+    fn accepts_timestamp_at_the_edge_of_the_skew_window() {
+        let (signing_key, public_key_hex) = test_keypair();
+        let timestamp = (Utc::now().timestamp() - DEFAULT_TIMESTAMP_SKEW_SECONDS).to_string();
+        let signature_hex = sign(&signing_key, &timestamp, "{}");
+
+        let result = verify_signature(&signature_hex, &timestamp, "{}", &public_key_hex, DEFAULT_TIMESTAMP_SKEW_SECONDS);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn rejects_signature_that_does_not_match_body() {
+        let (signing_key, public_key_hex) = test_keypair();
+        let timestamp = Utc::now().timestamp().to_string();
+        let signature_hex = sign(&signing_key, &timestamp, "{\"a\":1}");
+
+        let result = verify_signature(&signature_hex, &timestamp, "{\"a\":2}", &public_key_hex, DEFAULT_TIMESTAMP_SKEW_SECONDS);
+
+        assert!(matches!(result, Err(VerifyError::SignatureMismatch)));
+    }
+
+    #[test]
+    fn accepts_a_valid_signature() {
+        let (signing_key, public_key_hex) = test_keypair();
+        let timestamp = Utc::now().timestamp().to_string();
+        let body = "{\"type\":1}";
+        let signature_hex = sign(&signing_key, &timestamp, body);
+
+        let result = verify_signature(&signature_hex, &timestamp, body, &public_key_hex, DEFAULT_TIMESTAMP_SKEW_SECONDS);
+
+        assert!(result.is_ok());
     }
-}
\ No newline at end of file
+}
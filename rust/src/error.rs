@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+/// クレート全体で使う構造化エラー型。Discordへ返すephemeralメッセージへの
+/// 変換や、ログ上での原因の切り分けがしやすいようバリアントを分けている。
+#[derive(Debug, Error)]
+pub enum BotError {
+    #[error("signature verification failed: {0}")]
+    Verify(#[from] crate::discord::VerifyError),
+
+    #[error("DynamoDB operation failed: {0}")]
+    Database(#[source] anyhow::Error),
+
+    #[error("stock data provider failed: {0}")]
+    Quote(#[source] anyhow::Error),
+
+    #[error("invalid Discord interaction payload: {0}")]
+    InvalidInteraction(String),
+
+    #[error("unexpected error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl BotError {
+    /// ユーザーへ返すephemeralメッセージ。内部エラーの詳細は公開しない。
+    pub fn user_message(&self) -> String {
+        match self {
+            BotError::Verify(_) => "❌ 署名の検証に失敗しました".to_string(),
+            BotError::Database(_) => "❌ データの保存・取得に失敗しました".to_string(),
+            BotError::Quote(_) => "❌ 株価データの取得に失敗しました".to_string(),
+            BotError::InvalidInteraction(_) => "❌ リクエストの形式が不正です".to_string(),
+            BotError::Internal(_) => "❌ 処理中にエラーが発生しました".to_string(),
+        }
+    }
+}
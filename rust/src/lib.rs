@@ -0,0 +1,26 @@
+//! # アーキテクチャ上の判断: リアルタイム価格ストリーミングは実装しない
+//!
+//! [Gilfeather/stock-monitoring-bot#chunk0-2] はWebSocketによるリアルタイム価格配信
+//! （`PriceStream` + broadcastファンアウト + subscribe/unsubscribe）を要求していたが、
+//! 本クレートは`discord_handler`/`alert_scheduler`の2つのリクエストスコープLambdaのみで
+//! 構成されており、どちらも呼び出しの間は永続的なソケットを保持できない。一度試作した
+//! 実装（`streaming/mod.rs`、コミット146d709）はどちらのバイナリからも呼び出されない
+//! デッドコードのままになっていたため削除した（コミット31d217f）。
+//!
+//! リアルタイムストリーミングを実際に提供するには、WebSocket接続を保持し続けられる
+//! 常駐サービス（ECS/Fargateタスクなど）を別途用意し、価格更新をこのクレートの
+//! `alerts`/`database`が読めるストア（DynamoDB Streams、SNS/SQS等）に書き戻す構成が
+//! 必要になる。現状のLambdaオンリーな構成ではこのリクエストはwon't-do（実装見送り）
+//! とする。
+pub mod alerts;
+pub mod chart;
+pub mod config;
+pub mod database;
+pub mod discord;
+pub mod error;
+pub mod logging;
+pub mod market_clock;
+pub mod models;
+pub mod stocks;
+
+pub use error::BotError;
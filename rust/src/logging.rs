@@ -0,0 +1,24 @@
+use crate::config::Config;
+
+/// 両Lambdaバイナリ共通のログ初期化。`Config::log_level()`/`Config::log_json()`で
+/// 環境変数からレベル・出力形式を決定する。JSONはCloudWatch Logs Insightsでのクエリ向け、
+/// `LOG_FORMAT=pretty`はローカル実行など人間が読む場合向け。
+pub fn init() {
+    let level = Config::log_level();
+
+    if Config::log_json() {
+        tracing_subscriber::fmt()
+            .json()
+            .with_max_level(level)
+            .with_target(false)
+            .with_current_span(true)
+            .with_span_list(true)
+            .init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_target(false)
+            .without_time()
+            .init();
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+
+const EST_GMT_OFFSET_SECONDS: i32 = -5 * 3600;
+const EDT_GMT_OFFSET_SECONDS: i32 = -4 * 3600;
+
+/// Alpha VantageやFinnhubは取引所のタイムゾーンをレスポンスに含めないため、
+/// 米国株のフォールバック値として東部時間のオフセットを使う。サマータイム（EDT/EST）を
+/// 2007年以降の米国ルール（3月第2日曜〜11月第1日曜）で判定する。
+/// 切り替え時刻（現地時間午前2時）までは厳密に再現せず、UTC日付で判定する近似値。
+pub fn us_eastern_gmt_offset_seconds(at: DateTime<Utc>) -> i32 {
+    if is_us_dst(at.date_naive()) {
+        EDT_GMT_OFFSET_SECONDS
+    } else {
+        EST_GMT_OFFSET_SECONDS
+    }
+}
+
+fn is_us_dst(date: NaiveDate) -> bool {
+    let dst_start = nth_sunday(date.year(), 3, 2);
+    let dst_end = nth_sunday(date.year(), 11, 1);
+    date >= dst_start && date < dst_end
+}
+
+/// `year`年`month`月の`n`番目の日曜日を返す（`n`は1始まり）。
+fn nth_sunday(year: i32, month: u32, n: u32) -> NaiveDate {
+    let first_of_month = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+    let days_until_sunday = (7 - first_of_month.weekday().num_days_from_sunday()) % 7;
+    let first_sunday = first_of_month + Duration::days(days_until_sunday as i64);
+    first_sunday + Duration::days(7 * (n as i64 - 1))
+}
+
+const PRE_MARKET_OPEN: (u32, u32) = (4, 0);
+const REGULAR_OPEN: (u32, u32) = (9, 30);
+const REGULAR_CLOSE: (u32, u32) = (16, 0);
+const AFTER_HOURS_CLOSE: (u32, u32) = (20, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketSession {
+    PreMarket,
+    Open,
+    AfterHours,
+    Closed,
+}
+
+/// 取引所のタイムゾーンを基準に、現在の市場セッションと次回の立会時刻を求めるヘルパー。
+/// 祝日カレンダーは持たないため、休日判定は週末のみを考慮する。
+pub struct MarketClock {
+    offset: FixedOffset,
+}
+
+impl MarketClock {
+    /// `gmt_offset_seconds` はYahoo Financeの `meta.gmtoffset` から取得した、
+    /// 取引所のUTCからのオフセット（秒）。
+    pub fn for_exchange(gmt_offset_seconds: i32) -> Self {
+        let offset = FixedOffset::east_opt(gmt_offset_seconds).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+        Self { offset }
+    }
+
+    pub fn session(&self, now: DateTime<Utc>) -> MarketSession {
+        let local = now.with_timezone(&self.offset);
+
+        if is_weekend(local.weekday()) {
+            return MarketSession::Closed;
+        }
+
+        let minutes = local.hour() * 60 + local.minute();
+        if minutes < minutes_of(PRE_MARKET_OPEN) {
+            MarketSession::Closed
+        } else if minutes < minutes_of(REGULAR_OPEN) {
+            MarketSession::PreMarket
+        } else if minutes < minutes_of(REGULAR_CLOSE) {
+            MarketSession::Open
+        } else if minutes < minutes_of(AFTER_HOURS_CLOSE) {
+            MarketSession::AfterHours
+        } else {
+            MarketSession::Closed
+        }
+    }
+
+    /// 次回の通常取引開始時刻（週末をスキップ）をUTCで返す。
+    pub fn next_open(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let local = now.with_timezone(&self.offset);
+        let open_time = naive_time(REGULAR_OPEN);
+
+        let mut date = local.date_naive();
+        if is_weekend(date.weekday()) || local.time() >= open_time {
+            date = date.succ_opt().unwrap_or(date);
+        }
+        while is_weekend(date.weekday()) {
+            date = date.succ_opt().unwrap_or(date);
+        }
+
+        self.offset
+            .from_local_datetime(&date.and_time(open_time))
+            .single()
+            .unwrap_or(local)
+            .with_timezone(&Utc)
+    }
+
+    /// 次回の通常取引終了時刻（週末をスキップ）をUTCで返す。
+    pub fn next_close(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let local = now.with_timezone(&self.offset);
+        let close_time = naive_time(REGULAR_CLOSE);
+
+        let mut date = local.date_naive();
+        if is_weekend(date.weekday()) || local.time() >= close_time {
+            date = date.succ_opt().unwrap_or(date);
+            while is_weekend(date.weekday()) {
+                date = date.succ_opt().unwrap_or(date);
+            }
+        }
+
+        self.offset
+            .from_local_datetime(&date.and_time(close_time))
+            .single()
+            .unwrap_or(local)
+            .with_timezone(&Utc)
+    }
+}
+
+fn is_weekend(weekday: Weekday) -> bool {
+    matches!(weekday, Weekday::Sat | Weekday::Sun)
+}
+
+fn minutes_of((hour, minute): (u32, u32)) -> u32 {
+    hour * 60 + minute
+}
+
+fn naive_time((hour, minute): (u32, u32)) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
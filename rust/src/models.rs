@@ -7,6 +7,8 @@ pub struct DiscordInteraction {
     pub interaction_type: u8,
     pub id: Option<String>,
     pub application_id: Option<String>,
+    /// フォローアップWebhook（`/webhooks/{application_id}/{token}/messages/@original`）の送信に使う
+    pub token: Option<String>,
     pub data: Option<InteractionData>,
     pub member: Option<Member>,
     pub user: Option<User>,
@@ -14,8 +16,11 @@ pub struct DiscordInteraction {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InteractionData {
+    #[serde(default)]
     pub name: String,
     pub options: Option<Vec<CommandOption>>,
+    /// MESSAGE_COMPONENT（type 3）のボタン押下時に送られてくる識別子
+    pub custom_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,9 +50,98 @@ pub struct InteractionResponse {
 pub struct InteractionResponseData {
     pub content: String,
     pub flags: Option<u64>,
+    /// 画像等のファイル添付。Discordへの実送信はmultipart/form-dataで行う必要があるため
+    /// JSONシリアライズ対象からは除外し、送信経路側でこのフィールドを見て分岐する。
+    #[serde(skip)]
+    pub attachment: Option<ChartAttachment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<ActionRow>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+pub struct ChartAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// ボタンのスタイル（Discordの `ButtonStyle`）。
+/// Discordは`style`を数値として要求するため、variant名ではなく数値でシリアライズする。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+#[repr(u8)]
+pub enum ButtonStyle {
+    Primary = 1,
+    Secondary = 2,
+}
+
+impl From<ButtonStyle> for u8 {
+    fn from(style: ButtonStyle) -> Self {
+        style as u8
+    }
+}
+
+impl TryFrom<u8> for ButtonStyle {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(ButtonStyle::Primary),
+            2 => Ok(ButtonStyle::Secondary),
+            other => Err(format!("unknown Discord button style: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Button {
+    #[serde(rename = "type")]
+    pub component_type: u8, // 2 = Button
+    pub style: ButtonStyle,
+    pub label: String,
+    pub custom_id: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub disabled: bool,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+impl Button {
+    pub fn new(label: impl Into<String>, custom_id: impl Into<String>) -> Self {
+        Self {
+            component_type: 2,
+            style: ButtonStyle::Secondary,
+            label: label.into(),
+            custom_id: custom_id.into(),
+            disabled: false,
+        }
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRow {
+    #[serde(rename = "type")]
+    pub component_type: u8, // 1 = Action Row
+    pub components: Vec<Button>,
+}
+
+impl ActionRow {
+    pub fn new(buttons: Vec<Button>) -> Self {
+        Self {
+            component_type: 1,
+            components: buttons,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StockPrice {
     pub symbol: String,
     pub timestamp: DateTime<Utc>,
@@ -59,6 +153,21 @@ pub struct StockPrice {
     pub previous_close: Option<f64>,
     pub change: Option<f64>,
     pub change_percent: Option<f64>,
+    /// キャッシュ上限を超えても上流フェッチが失敗したため、古い値を代わりに返している場合true
+    #[serde(default)]
+    pub is_stale: bool,
+    /// 取引所のタイムゾーン名（例: "America/New_York"）。取得元が対応していない場合None
+    #[serde(default)]
+    pub exchange_timezone: Option<String>,
+    /// UTCからの取引所オフセット（秒）。`MarketClock`のセッション判定に使う
+    #[serde(default)]
+    pub gmt_offset_seconds: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertDirection {
+    Above,
+    Below,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,6 +176,9 @@ pub struct WatchlistItem {
     pub symbol: String,
     pub added_at: DateTime<Utc>,
     pub alert_threshold: Option<f64>,
+    pub alert_direction: Option<AlertDirection>,
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    pub last_triggered_price: Option<f64>,
 }
 
 impl InteractionResponse {
@@ -83,6 +195,8 @@ impl InteractionResponse {
             data: Some(InteractionResponseData {
                 content,
                 flags: None,
+                attachment: None,
+                components: None,
             }),
         }
     }
@@ -93,6 +207,54 @@ impl InteractionResponse {
             data: Some(InteractionResponseData {
                 content,
                 flags: Some(64), // EPHEMERAL
+                attachment: None,
+                components: None,
+            }),
+        }
+    }
+
+    pub fn message_with_attachment(content: String, attachment: ChartAttachment) -> Self {
+        Self {
+            response_type: 4, // CHANNEL_MESSAGE_WITH_SOURCE
+            data: Some(InteractionResponseData {
+                content,
+                flags: None,
+                attachment: Some(attachment),
+                components: None,
+            }),
+        }
+    }
+
+    pub fn message_with_components(content: String, components: Vec<ActionRow>) -> Self {
+        Self {
+            response_type: 4, // CHANNEL_MESSAGE_WITH_SOURCE
+            data: Some(InteractionResponseData {
+                content,
+                flags: None,
+                attachment: None,
+                components: Some(components),
+            }),
+        }
+    }
+
+    /// 3秒のACK期限に間に合わない処理向け。即座にこれを返し、本当の内容は
+    /// フォローアップWebhook（`/webhooks/{application_id}/{token}/messages/@original`）で送る。
+    pub fn deferred() -> Self {
+        Self {
+            response_type: 5, // DEFERRED_CHANNEL_MESSAGE_WITH_SOURCE
+            data: None,
+        }
+    }
+
+    /// ボタン押下（MESSAGE_COMPONENT）への応答。新規メッセージを送らず元メッセージを書き換える。
+    pub fn update_message(content: String, components: Vec<ActionRow>) -> Self {
+        Self {
+            response_type: 7, // UPDATE_MESSAGE
+            data: Some(InteractionResponseData {
+                content,
+                flags: None,
+                attachment: None,
+                components: Some(components),
             }),
         }
     }
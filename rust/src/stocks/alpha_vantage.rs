@@ -0,0 +1,227 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use crate::market_clock::us_eastern_gmt_offset_seconds;
+use crate::models::StockPrice;
+use crate::stocks::provider::QuoteProvider;
+
+/// Alpha Vantageの`GLOBAL_QUOTE`レスポンス。数値が常に文字列で返ってくるため、
+/// Finnhubプロバイダと同様にresilientな数値デシリアライズを使う。
+#[derive(Debug, Deserialize)]
+struct AlphaVantageQuote {
+    #[serde(rename = "05. price", deserialize_with = "deserialize_number_from_string")]
+    price: f64,
+    #[serde(rename = "02. open", default, deserialize_with = "deserialize_number_from_string")]
+    open: f64,
+    #[serde(rename = "03. high", default, deserialize_with = "deserialize_number_from_string")]
+    high: f64,
+    #[serde(rename = "04. low", default, deserialize_with = "deserialize_number_from_string")]
+    low: f64,
+    #[serde(rename = "06. volume", default, deserialize_with = "deserialize_number_from_string")]
+    volume: u64,
+    #[serde(rename = "08. previous close", default, deserialize_with = "deserialize_number_from_string")]
+    previous_close: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlphaVantageGlobalQuoteResponse {
+    #[serde(rename = "Global Quote")]
+    global_quote: Option<AlphaVantageQuote>,
+}
+
+/// `TIME_SERIES_DAILY`の1日分のバー。同じく数値は文字列で返ってくる。
+#[derive(Debug, Deserialize)]
+struct AlphaVantageDailyBar {
+    #[serde(rename = "1. open", default, deserialize_with = "deserialize_number_from_string")]
+    open: f64,
+    #[serde(rename = "2. high", default, deserialize_with = "deserialize_number_from_string")]
+    high: f64,
+    #[serde(rename = "3. low", default, deserialize_with = "deserialize_number_from_string")]
+    low: f64,
+    #[serde(rename = "4. close", deserialize_with = "deserialize_number_from_string")]
+    close: f64,
+    #[serde(rename = "5. volume", default, deserialize_with = "deserialize_number_from_string")]
+    volume: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlphaVantageDailySeriesResponse {
+    /// 日付文字列（"YYYY-MM-DD"）をキーとする昇順ソート済みマップ。`BTreeMap`なので
+    /// キーの辞書順ソート＝日付の昇順ソートになる。`Value`のまま受け取り、1日分ずつ
+    /// 個別にデシリアライズする（1日分の欠損・パース不能で履歴全体を失敗させないため）。
+    #[serde(rename = "Time Series (Daily)")]
+    time_series: Option<BTreeMap<String, Value>>,
+}
+
+pub struct AlphaVantageProvider {
+    client: Client,
+    api_key: String,
+}
+
+impl AlphaVantageProvider {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+        }
+    }
+
+    /// `period` (例: "1mo", "6mo", "1y") を日数に変換する。
+    fn period_to_days(period: &str) -> i64 {
+        match period {
+            "1mo" => 30,
+            "3mo" => 90,
+            "6mo" => 180,
+            "1y" => 365,
+            _ => 90,
+        }
+    }
+}
+
+#[async_trait]
+impl QuoteProvider for AlphaVantageProvider {
+    fn name(&self) -> &'static str {
+        "alpha_vantage"
+    }
+
+    async fn validate_symbol(&self, symbol: &str) -> Result<bool> {
+        let url = format!(
+            "https://www.alphavantage.co/query?function=GLOBAL_QUOTE&symbol={}&apikey={}",
+            symbol, self.api_key
+        );
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Ok(false);
+        }
+
+        let data: Value = response.json().await?;
+        let quote = data.get("Global Quote").and_then(|q| q.as_object());
+
+        Ok(quote.is_some_and(|q| !q.is_empty()))
+    }
+
+    async fn current_price(&self, symbol: &str) -> Result<StockPrice> {
+        let url = format!(
+            "https://www.alphavantage.co/query?function=GLOBAL_QUOTE&symbol={}&apikey={}",
+            symbol, self.api_key
+        );
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to fetch stock data: HTTP {}", response.status()));
+        }
+
+        let response: AlphaVantageGlobalQuoteResponse = response.json().await?;
+
+        let quote = response.global_quote
+            .ok_or_else(|| anyhow!("Missing Global Quote for symbol: {}", symbol))?;
+
+        let previous_close = (quote.previous_close != 0.0).then_some(quote.previous_close);
+
+        let (change, change_percent) = if let Some(prev_close) = previous_close {
+            let change_val = quote.price - prev_close;
+            let change_pct = (change_val / prev_close) * 100.0;
+            (Some(change_val), Some(change_pct))
+        } else {
+            (None, None)
+        };
+
+        let timestamp = Utc::now();
+
+        Ok(StockPrice {
+            symbol: symbol.to_string(),
+            timestamp,
+            price: quote.price,
+            open_price: Some(quote.open),
+            high_price: Some(quote.high),
+            low_price: Some(quote.low),
+            volume: Some(quote.volume),
+            previous_close,
+            change,
+            change_percent,
+            is_stale: false,
+            // Alpha Vantageのレスポンスには取引所のタイムゾーンが含まれないため、
+            // 米国株を前提にデフォルトの東部時間オフセットを使う。
+            exchange_timezone: Some("America/New_York".to_string()),
+            gmt_offset_seconds: Some(us_eastern_gmt_offset_seconds(timestamp)),
+        })
+    }
+
+    async fn historical(&self, symbol: &str, period: &str) -> Result<Vec<StockPrice>> {
+        let days = Self::period_to_days(period);
+        // コンパクト出力（直近100営業日分）では6mo/1yを賄えないため、必要な場合のみfullを使う
+        let output_size = if days > 100 { "full" } else { "compact" };
+
+        let url = format!(
+            "https://www.alphavantage.co/query?function=TIME_SERIES_DAILY&symbol={}&outputsize={}&apikey={}",
+            symbol, output_size, self.api_key
+        );
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to fetch historical data: HTTP {}", response.status()));
+        }
+
+        let response: AlphaVantageDailySeriesResponse = response.json().await?;
+
+        let series = response.time_series
+            .ok_or_else(|| anyhow!("No historical data found for symbol: {}", symbol))?;
+
+        let cutoff = Utc::now() - Duration::days(days);
+
+        let mut prices = Vec::new();
+        let mut previous_close: Option<f64> = None;
+
+        for (date, raw_bar) in series {
+            // 1日分だけ値が欠けている/数値としてパースできない場合でも、その日をスキップ
+            // するだけで履歴全体を取得失敗にはしない。
+            let Ok(bar) = serde_json::from_value::<AlphaVantageDailyBar>(raw_bar) else { continue };
+            let close = bar.close;
+
+            let datetime = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc())
+                .unwrap_or_else(Utc::now);
+
+            let (change, change_percent) = if let Some(prev_close) = previous_close {
+                let change_val = close - prev_close;
+                let change_pct = (change_val / prev_close) * 100.0;
+                (Some(change_val), Some(change_pct))
+            } else {
+                (None, None)
+            };
+
+            if datetime >= cutoff {
+                prices.push(StockPrice {
+                    symbol: symbol.to_string(),
+                    timestamp: datetime,
+                    price: close,
+                    open_price: Some(bar.open),
+                    high_price: Some(bar.high),
+                    low_price: Some(bar.low),
+                    volume: Some(bar.volume),
+                    previous_close,
+                    change,
+                    change_percent,
+                    is_stale: false,
+                    exchange_timezone: None,
+                    gmt_offset_seconds: None,
+                });
+            }
+
+            previous_close = Some(close);
+        }
+
+        Ok(prices)
+    }
+}
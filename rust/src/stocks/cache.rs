@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use dashmap::DashMap;
+
+use crate::models::StockPrice;
+use crate::stocks::provider::QuoteProvider;
+
+const DEFAULT_PRICE_TTL: Duration = Duration::from_secs(30);
+const DEFAULT_VALIDATE_TTL: Duration = Duration::from_secs(3600);
+const MAX_STALE_AGE: Duration = Duration::from_secs(300);
+
+/// 任意のQuoteProviderをラップし、symbol単位でTTL付きキャッシュを行う。
+/// 同じ銘柄への短時間の連続リクエスト（/price, /add, アラート評価など）で
+/// 上流に429を出させないためのもの。上流が失敗した場合でも、ハード上限
+/// （MAX_STALE_AGE）内であれば古い値を stale としてそのまま返す。
+pub struct CachedProvider<P: QuoteProvider> {
+    inner: P,
+    price_ttl: Duration,
+    validate_ttl: Duration,
+    prices: DashMap<String, (StockPrice, Instant)>,
+    validations: DashMap<String, (bool, Instant)>,
+}
+
+impl<P: QuoteProvider> CachedProvider<P> {
+    pub fn new(inner: P) -> Self {
+        Self::with_ttl(inner, DEFAULT_PRICE_TTL, DEFAULT_VALIDATE_TTL)
+    }
+
+    pub fn with_ttl(inner: P, price_ttl: Duration, validate_ttl: Duration) -> Self {
+        Self {
+            inner,
+            price_ttl,
+            validate_ttl,
+            prices: DashMap::new(),
+            validations: DashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl<P: QuoteProvider> QuoteProvider for CachedProvider<P> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn validate_symbol(&self, symbol: &str) -> Result<bool> {
+        if let Some(entry) = self.validations.get(symbol) {
+            let (valid, fetched_at) = *entry;
+            if fetched_at.elapsed() < self.validate_ttl {
+                return Ok(valid);
+            }
+        }
+
+        let valid = self.inner.validate_symbol(symbol).await?;
+        self.validations.insert(symbol.to_string(), (valid, Instant::now()));
+        Ok(valid)
+    }
+
+    async fn current_price(&self, symbol: &str) -> Result<StockPrice> {
+        if let Some(entry) = self.prices.get(symbol) {
+            let (price, fetched_at) = entry.clone();
+            if fetched_at.elapsed() < self.price_ttl {
+                return Ok(price);
+            }
+        }
+
+        match self.inner.current_price(symbol).await {
+            Ok(price) => {
+                self.prices.insert(symbol.to_string(), (price.clone(), Instant::now()));
+                Ok(price)
+            }
+            Err(e) => {
+                if let Some(entry) = self.prices.get(symbol) {
+                    let (price, fetched_at) = entry.clone();
+                    if fetched_at.elapsed() < MAX_STALE_AGE {
+                        tracing::warn!("Upstream fetch failed for {}, serving stale cached price: {}", symbol, e);
+                        return Ok(StockPrice { is_stale: true, ..price });
+                    }
+                }
+
+                Err(e)
+            }
+        }
+    }
+
+    async fn historical(&self, symbol: &str, period: &str) -> Result<Vec<StockPrice>> {
+        self.inner.historical(symbol, period).await
+    }
+}
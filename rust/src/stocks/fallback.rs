@@ -0,0 +1,91 @@
+use std::time::Instant;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use crate::models::StockPrice;
+use crate::stocks::provider::QuoteProvider;
+
+/// 複数のQuoteProviderを優先順位付きで試す。先頭から順に試し、
+/// HTTPエラーや空の結果が返ってきた場合は次のプロバイダにフォールバックする。
+pub struct FallbackProvider {
+    providers: Vec<Box<dyn QuoteProvider>>,
+}
+
+impl FallbackProvider {
+    pub fn new(providers: Vec<Box<dyn QuoteProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl QuoteProvider for FallbackProvider {
+    fn name(&self) -> &'static str {
+        "fallback"
+    }
+
+    async fn validate_symbol(&self, symbol: &str) -> Result<bool> {
+        for provider in &self.providers {
+            let started_at = Instant::now();
+            let outcome = provider.validate_symbol(symbol).await;
+            tracing::info!(provider = provider.name(), elapsed_ms = started_at.elapsed().as_millis() as u64, "quote provider call (validate_symbol)");
+
+            match outcome {
+                Ok(true) => return Ok(true),
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!("Provider {} failed on validate_symbol, trying next: {}", provider.name(), e);
+                    continue;
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    async fn current_price(&self, symbol: &str) -> Result<StockPrice> {
+        let mut last_error = None;
+
+        for provider in &self.providers {
+            let started_at = Instant::now();
+            let outcome = provider.current_price(symbol).await;
+            tracing::info!(provider = provider.name(), elapsed_ms = started_at.elapsed().as_millis() as u64, "quote provider call (current_price)");
+
+            match outcome {
+                Ok(price) => return Ok(price),
+                Err(e) => {
+                    tracing::warn!("Provider {} failed on current_price, trying next: {}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("No providers configured")))
+    }
+
+    async fn historical(&self, symbol: &str, period: &str) -> Result<Vec<StockPrice>> {
+        let mut last_error = None;
+
+        for provider in &self.providers {
+            let started_at = Instant::now();
+            let outcome = provider.historical(symbol, period).await;
+            tracing::info!(provider = provider.name(), elapsed_ms = started_at.elapsed().as_millis() as u64, "quote provider call (historical)");
+
+            match outcome {
+                Ok(prices) if !prices.is_empty() => return Ok(prices),
+                Ok(_) => {
+                    tracing::warn!("Provider {} returned no historical data, trying next", provider.name());
+                }
+                Err(e) => {
+                    tracing::warn!("Provider {} failed on historical, trying next: {}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) => Err(e),
+            None if self.providers.is_empty() => Err(anyhow!("No providers configured")),
+            None => Ok(Vec::new()),
+        }
+    }
+}
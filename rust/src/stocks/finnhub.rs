@@ -0,0 +1,195 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+use crate::market_clock::us_eastern_gmt_offset_seconds;
+use crate::models::StockPrice;
+use crate::stocks::provider::QuoteProvider;
+
+/// Finnhubの `/quote` レスポンス。プランによっては数値が文字列で返ることもあるため、
+/// 文字列・数値のどちらでも受理できるようにしている。
+#[derive(Debug, Deserialize)]
+struct FinnhubQuote {
+    #[serde(rename = "c", deserialize_with = "deserialize_number_from_string")]
+    current: f64,
+    #[serde(rename = "o", default, deserialize_with = "deserialize_number_from_string")]
+    open: f64,
+    #[serde(rename = "h", default, deserialize_with = "deserialize_number_from_string")]
+    high: f64,
+    #[serde(rename = "l", default, deserialize_with = "deserialize_number_from_string")]
+    low: f64,
+    #[serde(rename = "pc", default, deserialize_with = "deserialize_number_from_string")]
+    previous_close: f64,
+    /// UNIXタイムスタンプ。存在しない/0の場合は未知の銘柄とみなす
+    #[serde(rename = "t", default)]
+    timestamp: i64,
+}
+
+/// Finnhubの `/stock/candle` レスポンス。`s` が "no_data" の場合は該当期間のデータなし
+#[derive(Debug, Deserialize)]
+struct FinnhubCandles {
+    #[serde(default)]
+    c: Vec<f64>,
+    #[serde(default)]
+    o: Vec<f64>,
+    #[serde(default)]
+    h: Vec<f64>,
+    #[serde(default)]
+    l: Vec<f64>,
+    #[serde(default)]
+    v: Vec<u64>,
+    #[serde(default)]
+    t: Vec<i64>,
+    s: String,
+}
+
+pub struct FinnhubProvider {
+    client: Client,
+    api_key: String,
+}
+
+impl FinnhubProvider {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+        }
+    }
+
+    async fn fetch_quote(&self, symbol: &str) -> Result<FinnhubQuote> {
+        let url = format!(
+            "https://finnhub.io/api/v1/quote?symbol={}&token={}",
+            symbol, self.api_key
+        );
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to fetch stock data: HTTP {}", response.status()));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    fn period_to_days(period: &str) -> i64 {
+        match period {
+            "1mo" => 30,
+            "3mo" => 90,
+            "6mo" => 180,
+            "1y" => 365,
+            _ => 90,
+        }
+    }
+}
+
+#[async_trait]
+impl QuoteProvider for FinnhubProvider {
+    fn name(&self) -> &'static str {
+        "finnhub"
+    }
+
+    async fn validate_symbol(&self, symbol: &str) -> Result<bool> {
+        let quote = self.fetch_quote(symbol).await?;
+        Ok(quote.timestamp != 0)
+    }
+
+    async fn current_price(&self, symbol: &str) -> Result<StockPrice> {
+        let quote = self.fetch_quote(symbol).await?;
+
+        if quote.timestamp == 0 {
+            return Err(anyhow!("No data found for symbol: {}", symbol));
+        }
+
+        let change = quote.current - quote.previous_close;
+        let change_percent = if quote.previous_close != 0.0 {
+            Some((change / quote.previous_close) * 100.0)
+        } else {
+            None
+        };
+
+        let timestamp = Utc.timestamp_opt(quote.timestamp, 0).single().unwrap_or_else(Utc::now);
+
+        Ok(StockPrice {
+            symbol: symbol.to_string(),
+            timestamp,
+            price: quote.current,
+            open_price: Some(quote.open),
+            high_price: Some(quote.high),
+            low_price: Some(quote.low),
+            volume: None, // Finnhubの/quoteは出来高を返さない
+            previous_close: Some(quote.previous_close),
+            change: Some(change),
+            change_percent,
+            is_stale: false,
+            // Finnhubの`/quote`レスポンスには取引所のタイムゾーンが含まれないため、
+            // 米国株を前提にデフォルトの東部時間オフセットを使う。
+            exchange_timezone: Some("America/New_York".to_string()),
+            gmt_offset_seconds: Some(us_eastern_gmt_offset_seconds(timestamp)),
+        })
+    }
+
+    async fn historical(&self, symbol: &str, period: &str) -> Result<Vec<StockPrice>> {
+        let days = Self::period_to_days(period);
+        let to = Utc::now().timestamp();
+        let from = to - days * 24 * 60 * 60;
+
+        let url = format!(
+            "https://finnhub.io/api/v1/stock/candle?symbol={}&resolution=D&from={}&to={}&token={}",
+            symbol, from, to, self.api_key
+        );
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to fetch historical data: HTTP {}", response.status()));
+        }
+
+        let candles: FinnhubCandles = response.json().await?;
+
+        if candles.s != "ok" {
+            return Err(anyhow!("No historical data found for symbol: {}", symbol));
+        }
+
+        let mut prices = Vec::new();
+        let mut previous_close: Option<f64> = None;
+
+        for i in 0..candles.t.len() {
+            let close_price = match candles.c.get(i) {
+                Some(price) => *price,
+                None => continue,
+            };
+
+            let timestamp = Utc.timestamp_opt(candles.t[i], 0).single().unwrap_or_else(Utc::now);
+
+            let (change, change_percent) = if let Some(prev_close) = previous_close {
+                let change_val = close_price - prev_close;
+                let change_pct = (change_val / prev_close) * 100.0;
+                (Some(change_val), Some(change_pct))
+            } else {
+                (None, None)
+            };
+
+            prices.push(StockPrice {
+                symbol: symbol.to_string(),
+                timestamp,
+                price: close_price,
+                open_price: candles.o.get(i).copied(),
+                high_price: candles.h.get(i).copied(),
+                low_price: candles.l.get(i).copied(),
+                volume: candles.v.get(i).copied(),
+                previous_close,
+                change,
+                change_percent,
+                is_stale: false,
+                exchange_timezone: None,
+                gmt_offset_seconds: None,
+            });
+
+            previous_close = Some(close_price);
+        }
+
+        Ok(prices)
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use crate::models::StockPrice;
+
+/// `quotes()` のデフォルト実装で同時に問い合わせる銘柄数の上限。
+/// スケジューラのタイムアウト内に収めつつ上流レートリミットを避けるためのバッチサイズ。
+const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// 株価データソースを抽象化するトレイト。Yahoo Finance、Alpha Vantageなど
+/// 複数のプロバイダを同じインターフェースで扱えるようにする。
+#[async_trait]
+pub trait QuoteProvider: Send + Sync {
+    /// ログ・メトリクスでプロバイダを識別するための名前（CloudWatchで遅いプロバイダを特定するため）。
+    fn name(&self) -> &'static str;
+    async fn validate_symbol(&self, symbol: &str) -> Result<bool>;
+    async fn current_price(&self, symbol: &str) -> Result<StockPrice>;
+    async fn historical(&self, symbol: &str, period: &str) -> Result<Vec<StockPrice>>;
+
+    /// 複数銘柄の現在値をまとめて取得する。個々の上流APIは銘柄単位のエンドポイントしか
+    /// 持たないため、`current_price`を束ねて並列に呼び出すデフォルト実装を提供する。
+    /// 1銘柄の失敗が他の銘柄の結果に影響しないよう、戻り値は銘柄ごとのResultにする。
+    async fn quotes(&self, symbols: &[&str]) -> Vec<Result<StockPrice>> {
+        let mut results = Vec::with_capacity(symbols.len());
+
+        for chunk in symbols.chunks(DEFAULT_BATCH_CONCURRENCY) {
+            let mut batch = Vec::with_capacity(chunk.len());
+            for symbol in chunk {
+                let symbol = symbol.to_string();
+                batch.push(async move { self.current_price(&symbol).await });
+            }
+            results.extend(join_all(batch).await);
+        }
+
+        results
+    }
+}